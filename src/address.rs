@@ -0,0 +1,121 @@
+//! The 64-bit ROM address identifying a 1-Wire device.
+
+use crate::crc;
+
+/// A 1-Wire device ROM address: an 8-bit family code, a 48-bit serial number and an
+/// 8-bit CRC, packed little-endian (family code first) into a `u64`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Address(pub u64);
+
+impl Address {
+    /// Builds an address from its raw ROM bytes, little-endian (family code first, CRC last).
+    pub fn from_bytes(bytes: [u8; 8]) -> Address {
+        Address(u64::from_le_bytes(bytes))
+    }
+
+    /// Returns the raw ROM bytes, little-endian (family code first, CRC last).
+    pub fn to_bytes(&self) -> [u8; 8] {
+        self.0.to_le_bytes()
+    }
+
+    /// Returns the 8-bit family code identifying the device type (e.g. `0x28` for the DS18B20).
+    pub fn family_code(&self) -> u8 {
+        self.to_bytes()[0]
+    }
+
+    /// Returns the 48-bit serial number uniquely identifying the device within its family.
+    pub fn serial_number(&self) -> [u8; 6] {
+        let bytes = self.to_bytes();
+        [bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6]]
+    }
+
+    /// Returns the 8-bit CRC-8 of the family code and serial number.
+    pub fn crc(&self) -> u8 {
+        self.to_bytes()[7]
+    }
+
+    /// Returns whether [`Self::crc`] is the correct CRC-8 of the family code and serial number,
+    /// rejecting addresses corrupted by bus noise or a device unplugged mid-search.
+    pub fn is_valid(&self) -> bool {
+        crc::check_crc8(&self.to_bytes()).is_ok()
+    }
+}
+
+impl core::fmt::Display for Address {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let bytes = self.to_bytes();
+        for (i, byte) in bytes.iter().enumerate() {
+            if i > 0 {
+                write!(f, ":")?;
+            }
+            write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl defmt::Format for Address {
+    fn format(&self, f: defmt::Formatter) {
+        let bytes = self.to_bytes();
+        defmt::write!(
+            f,
+            "{=u8:02X}:{=u8:02X}:{=u8:02X}:{=u8:02X}:{=u8:02X}:{=u8:02X}:{=u8:02X}:{=u8:02X}",
+            bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn valid_rom(family: u8, serial: [u8; 6]) -> [u8; 8] {
+        let mut body = [0u8; 7];
+        body[0] = family;
+        body[1..].copy_from_slice(&serial);
+        let crc = crc::crc8(&body);
+
+        let mut bytes = [0u8; 8];
+        bytes[..7].copy_from_slice(&body);
+        bytes[7] = crc;
+        bytes
+    }
+
+    #[test]
+    fn from_bytes_to_bytes_round_trips() {
+        let bytes = valid_rom(0x28, [0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+        assert_eq!(Address::from_bytes(bytes).to_bytes(), bytes);
+    }
+
+    #[test]
+    fn accessors_split_the_rom_into_its_fields() {
+        let bytes = valid_rom(0x28, [0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+        let address = Address::from_bytes(bytes);
+
+        assert_eq!(address.family_code(), 0x28);
+        assert_eq!(address.serial_number(), [0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+        assert_eq!(address.crc(), bytes[7]);
+    }
+
+    #[test]
+    fn is_valid_accepts_a_correct_crc() {
+        let bytes = valid_rom(0x28, [0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+        assert!(Address::from_bytes(bytes).is_valid());
+    }
+
+    #[test]
+    fn is_valid_rejects_a_corrupt_crc() {
+        let mut bytes = valid_rom(0x28, [0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+        bytes[7] ^= 0xFF;
+        assert!(!Address::from_bytes(bytes).is_valid());
+    }
+
+    #[test]
+    fn display_formats_bytes_as_colon_separated_hex() {
+        let bytes = valid_rom(0x28, [0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+        let address = Address::from_bytes(bytes);
+
+        assert_eq!(address.to_string(), "28:01:02:03:04:05:06:9E");
+    }
+}
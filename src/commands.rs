@@ -0,0 +1,22 @@
+//! Well-known 1-Wire ROM commands.
+
+/// Searches for all devices on the bus, regardless of alarm state.
+pub const SEARCH_NORMAL: u8 = 0xF0;
+
+/// Searches only for devices that are currently in an alarm state.
+pub const SEARCH_ALARM: u8 = 0xEC;
+
+/// Reads the 64-bit ROM code of the single device on the bus.
+pub const READ_ROM: u8 = 0x33;
+
+/// Addresses a single device by its 64-bit ROM code.
+pub const MATCH_ROM: u8 = 0x55;
+
+/// Addresses all devices on the bus simultaneously.
+pub const SKIP_ROM: u8 = 0xCC;
+
+/// Switches all devices on the bus into Overdrive mode and addresses them all simultaneously.
+pub const OVERDRIVE_SKIP_ROM: u8 = 0x3C;
+
+/// Switches a specific device into Overdrive mode and addresses it by its 64-bit ROM code.
+pub const OVERDRIVE_MATCH_ROM: u8 = 0x69;
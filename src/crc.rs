@@ -0,0 +1,81 @@
+//! CRC helpers for validating 1-Wire ROM codes and data transfers.
+
+/// Computes the Dallas/Maxim CRC-8 (polynomial 0x31, reflected) over `data`.
+pub fn crc8(data: &[u8]) -> u8 {
+    let mut crc: u8 = 0;
+    for &byte in data {
+        let mut byte = byte;
+        for _ in 0..8 {
+            let mix = (crc ^ byte) & 0x01;
+            crc >>= 1;
+            if mix != 0 {
+                crc ^= 0x8C;
+            }
+            byte >>= 1;
+        }
+    }
+    crc
+}
+
+/// Checks that the last byte of `data` is the correct CRC-8 of the preceding bytes.
+pub fn check_crc8(data: &[u8]) -> Result<(), ()> {
+    let (body, crc) = data.split_at(data.len() - 1);
+    if crc8(body) == crc[0] {
+        Ok(())
+    } else {
+        Err(())
+    }
+}
+
+/// Computes the Dallas/Maxim CRC-16 (polynomial 0xA001, reflected) over `data`.
+/// Used to protect larger payloads, such as scratchpad and memory-page reads, where CRC-8
+/// would be too weak.
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in data {
+        let mut byte = byte;
+        for _ in 0..8 {
+            let mix = (crc as u8 ^ byte) & 0x01;
+            crc >>= 1;
+            if mix != 0 {
+                crc ^= 0xA001;
+            }
+            byte >>= 1;
+        }
+    }
+    crc
+}
+
+/// Checks that `inverted_crc`, transmitted least-significant-byte first, is the bitwise-inverted
+/// CRC-16 of `body` (as devices such as the DS2408/DS2423/DS28EC20 do).
+pub fn check_crc16(body: &[u8], inverted_crc: [u8; 2]) -> Result<(), ()> {
+    let expected = crc16(body) ^ 0xFFFF;
+    let actual = u16::from_le_bytes(inverted_crc);
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_crc16_accepts_a_correct_trailing_crc() {
+        let body = [0x01, 0x02, 0x03, 0x04];
+        let crc_bytes = (crc16(&body) ^ 0xFFFF).to_le_bytes();
+
+        assert_eq!(check_crc16(&body, crc_bytes), Ok(()));
+    }
+
+    #[test]
+    fn check_crc16_rejects_a_corrupt_trailing_crc() {
+        let body = [0x01, 0x02, 0x03, 0x04];
+        let mut crc_bytes = (crc16(&body) ^ 0xFFFF).to_le_bytes();
+        crc_bytes[0] ^= 0xFF;
+
+        assert_eq!(check_crc16(&body, crc_bytes), Err(()));
+    }
+}
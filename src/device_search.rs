@@ -1,7 +1,12 @@
 use embedded_hal::delay::DelayNs;
+#[cfg(feature = "async")]
+use embedded_hal_async::delay::DelayNs as AsyncDelayNs;
 
 use crate::{commands, crc, OneWire};
+#[cfg(feature = "async")]
+use crate::OneWireAsync;
 use crate::address::Address;
+use crate::error::ErrorKind;
 use crate::triplet::Triplet;
 
 pub struct DeviceSearch<'a, O, D>
@@ -15,13 +20,14 @@ pub struct DeviceSearch<'a, O, D>
 
 impl<'a, O, D> DeviceSearch<'a, O, D>
     where O: OneWire,
+          O::Error: From<ErrorKind>,
           D: DelayNs,
 {
     pub fn new(
         only_alarming: bool,
         one_wire: &'a mut O,
         delay: &'a mut D,
-    ) -> impl Iterator<Item=Result<Address, O::Error>> + 'a
+    ) -> Self
     {
         Self {
             one_wire,
@@ -32,15 +38,68 @@ impl<'a, O, D> DeviceSearch<'a, O, D>
         }
     }
 
+    /// Returns an iterator that only enumerates devices whose family code (the low byte of
+    /// the ROM) matches `family`, per Maxim application note 187. If no device of the
+    /// requested family is present, the iterator yields no items.
+    pub fn with_family(
+        family: u8,
+        only_alarming: bool,
+        one_wire: &'a mut O,
+        delay: &'a mut D,
+    ) -> Self
+    {
+        Self {
+            one_wire,
+            delay,
+            state: Some(SearchState::for_family(family)),
+            finished: false,
+            search_command: if only_alarming { commands::SEARCH_ALARM } else { commands::SEARCH_NORMAL },
+        }
+    }
+
+    /// Resumes a search from a [`SearchState`] previously obtained from this iterator via
+    /// [`Self::state`] (e.g. by a caller that stopped iterating early to interleave other bus
+    /// traffic), continuing enumeration instead of restarting from the lowest address.
+    pub fn resume(
+        state: SearchState,
+        only_alarming: bool,
+        one_wire: &'a mut O,
+        delay: &'a mut D,
+    ) -> Self
+    {
+        Self {
+            one_wire,
+            delay,
+            state: Some(state),
+            finished: false,
+            search_command: if only_alarming { commands::SEARCH_ALARM } else { commands::SEARCH_NORMAL },
+        }
+    }
+
+    /// Returns the state of the search after the most recently yielded device, or `None` once
+    /// the search has finished. Before the first item it is `None` for an iterator built with
+    /// [`Self::new`], and the seeded or resumed state for one built with [`Self::with_family`]
+    /// or [`Self::resume`]. Persist this and hand it back to [`Self::resume`] to continue
+    /// enumeration later.
+    pub fn state(&self) -> Option<SearchState> {
+        self.state
+    }
+
     fn search(search_state: Option<&SearchState>, command: u8, one_wire: &mut O, delay: &mut D) -> Result<Option<(Address, SearchState)>, O::Error> {
         let first_time = search_state.is_none();
         let mut search_state = search_state.cloned().unwrap_or(SearchState::initial());
 
         // stop searching if there are no discrepancies left
-        if search_state.discrepancies == 0 && !first_time {
+        if search_state.discrepancies == 0 && !first_time && search_state.last_discrepancy_index == 0 {
             return Ok(None);
         }
 
+        // the pass that consumes the sentinel seeded by `SearchState::for_family`: bits 0..8
+        // are forced to the requested family code, and bits 8..64 must behave like a
+        // first-time exploration (choose 0 by default, recording any discrepancy found)
+        // instead of being compared against a real `last_discrepancy_index`
+        let family_seeding = !first_time && search_state.family_restricted && search_state.last_discrepancy_index == 64;
+
         // reset one-wire bus
         let presence_pulse_detected = one_wire.reset(delay)?;
         if !presence_pulse_detected {
@@ -52,9 +111,16 @@ impl<'a, O, D> DeviceSearch<'a, O, D>
 
         // do binary search for next device address
         for i in 0..64_u8 {
+            // bits 8..64 of the family-seeding pass have no real discrepancy history yet, so
+            // they must be treated like a first-time exploration
+            let effective_first_time = first_time || (family_seeding && i >= 8);
+
             // determine direction bit
-            let dir_bit = if first_time {
-                // first time searching, so always choose 0 in case of discrepancy
+            let dir_bit = if family_seeding && i < 8 {
+                // forced to the requested family code
+                search_state.addr_bit(i)
+            } else if effective_first_time {
+                // first time searching this bit, so always choose 0 in case of discrepancy
                 false
             } else if i < search_state.last_discrepancy_index() {
                 // follow same path as last time in case of discrepancy
@@ -73,10 +139,10 @@ impl<'a, O, D> DeviceSearch<'a, O, D>
             // update search state
             match triplet {
                 Triplet::Discrepancy(dir_bit) => {
-                    if first_time || i > search_state.last_discrepancy_index() {
+                    if effective_first_time || i > search_state.last_discrepancy_index() {
                         // discrepancy found, so set bit in discrepancies bitflags
                         search_state.set_discrepancy(i);
-                    } else if !first_time && i == search_state.last_discrepancy_index() {
+                    } else if !effective_first_time && i == search_state.last_discrepancy_index() {
                         // discrepancy found at last discrepancy index, so unset bit in discrepancies bitflags
                         search_state.unset_discrepancy(i);
                     }
@@ -84,6 +150,11 @@ impl<'a, O, D> DeviceSearch<'a, O, D>
                 }
                 Triplet::AllMatch(bit) => {
                     // all devices have the same bit at this position
+                    if search_state.family_restricted && i < 8 && bit != search_state.addr_bit(i) {
+                        // every device disagrees with the requested family code here, so no
+                        // device of that family is present on the bus
+                        return Ok(None);
+                    }
                     search_state.set_addr_bit(i, bit);
                 }
                 Triplet::NoDevicesFound => {
@@ -93,8 +164,9 @@ impl<'a, O, D> DeviceSearch<'a, O, D>
             }
         }
 
-        // TODO: do proper error handling
-        crc::check_crc8(&search_state.address.to_le_bytes()).unwrap();
+        search_state.refresh_last_discrepancy_index();
+
+        crc::check_crc8(&search_state.address.to_le_bytes()).map_err(|_| ErrorKind::CrcMismatch)?;
 
         Ok(Some((Address(search_state.address), search_state)))
     }
@@ -102,6 +174,7 @@ impl<'a, O, D> DeviceSearch<'a, O, D>
 
 impl<'a, O, D> Iterator for DeviceSearch<'a, O, D>
     where O: OneWire,
+          O::Error: From<ErrorKind>,
           D: DelayNs,
 {
     type Item = Result<Address, O::Error>;
@@ -131,13 +204,223 @@ impl<'a, O, D> Iterator for DeviceSearch<'a, O, D>
     }
 }
 
+/// Async counterpart of [`DeviceSearch`].
+///
+/// Unlike [`DeviceSearch`], this does not implement [`Iterator`] (there is no stable async
+/// iterator trait yet); instead, call [`DeviceSearchAsync::next`] directly.
+#[cfg(feature = "async")]
+pub struct DeviceSearchAsync<'a, O: ?Sized, D>
+{
+    one_wire: &'a mut O,
+    delay: &'a mut D,
+    state: Option<SearchState>,
+    finished: bool,
+    search_command: u8,
+}
+
+#[cfg(feature = "async")]
+impl<'a, O, D> DeviceSearchAsync<'a, O, D>
+    where O: OneWireAsync + ?Sized,
+          O::Error: From<ErrorKind>,
+          D: AsyncDelayNs,
+{
+    pub fn new(
+        only_alarming: bool,
+        one_wire: &'a mut O,
+        delay: &'a mut D,
+    ) -> Self
+    {
+        Self {
+            one_wire,
+            delay,
+            state: None,
+            finished: false,
+            search_command: if only_alarming { commands::SEARCH_ALARM } else { commands::SEARCH_NORMAL },
+        }
+    }
+
+    /// Returns a cursor that only enumerates devices whose family code (the low byte of the
+    /// ROM) matches `family`, per Maxim application note 187. If no device of the requested
+    /// family is present, the first `next().await` returns `None`.
+    pub fn with_family(
+        family: u8,
+        only_alarming: bool,
+        one_wire: &'a mut O,
+        delay: &'a mut D,
+    ) -> Self
+    {
+        Self {
+            one_wire,
+            delay,
+            state: Some(SearchState::for_family(family)),
+            finished: false,
+            search_command: if only_alarming { commands::SEARCH_ALARM } else { commands::SEARCH_NORMAL },
+        }
+    }
+
+    /// Resumes a search from a [`SearchState`] previously obtained from this cursor via
+    /// [`Self::state`] (e.g. by a caller that stopped iterating early to interleave other bus
+    /// traffic), continuing enumeration instead of restarting from the lowest address.
+    pub fn resume(
+        state: SearchState,
+        only_alarming: bool,
+        one_wire: &'a mut O,
+        delay: &'a mut D,
+    ) -> Self
+    {
+        Self {
+            one_wire,
+            delay,
+            state: Some(state),
+            finished: false,
+            search_command: if only_alarming { commands::SEARCH_ALARM } else { commands::SEARCH_NORMAL },
+        }
+    }
+
+    /// Returns the state of the search after the most recently yielded device, or `None` once
+    /// the search has finished. Before the first item it is `None` for a cursor built with
+    /// [`Self::new`], and the seeded or resumed state for one built with [`Self::with_family`]
+    /// or [`Self::resume`]. Persist this and hand it back to [`Self::resume`] to continue
+    /// enumeration later.
+    pub fn state(&self) -> Option<SearchState> {
+        self.state
+    }
+
+    /// Returns the next device address on the bus, or `None` once the bus has been fully
+    /// enumerated.
+    pub async fn next(&mut self) -> Option<Result<Address, O::Error>> {
+        if self.finished {
+            return None;
+        }
+
+        let result = Self::search(self.state.as_ref(), self.search_command, self.one_wire, self.delay).await;
+        match result {
+            Ok(Some((address, search_state))) => {
+                self.state = Some(search_state);
+                Some(Ok(address))
+            }
+            Ok(None) => {
+                self.state = None;
+                self.finished = true;
+                None
+            }
+            Err(err) => {
+                self.state = None;
+                self.finished = true;
+                Some(Err(err))
+            }
+        }
+    }
+
+    async fn search(search_state: Option<&SearchState>, command: u8, one_wire: &mut O, delay: &mut D) -> Result<Option<(Address, SearchState)>, O::Error> {
+        let first_time = search_state.is_none();
+        let mut search_state = search_state.cloned().unwrap_or(SearchState::initial());
+
+        // stop searching if there are no discrepancies left
+        if search_state.discrepancies == 0 && !first_time && search_state.last_discrepancy_index == 0 {
+            return Ok(None);
+        }
+
+        // the pass that consumes the sentinel seeded by `SearchState::for_family`: bits 0..8
+        // are forced to the requested family code, and bits 8..64 must behave like a
+        // first-time exploration (choose 0 by default, recording any discrepancy found)
+        // instead of being compared against a real `last_discrepancy_index`
+        let family_seeding = !first_time && search_state.family_restricted && search_state.last_discrepancy_index == 64;
+
+        // reset one-wire bus
+        let presence_pulse_detected = one_wire.reset(delay).await?;
+        if !presence_pulse_detected {
+            return Ok(None);
+        }
+
+        // send search command
+        one_wire.write_byte(command, delay).await?;
+
+        // do binary search for next device address
+        for i in 0..64_u8 {
+            // bits 8..64 of the family-seeding pass have no real discrepancy history yet, so
+            // they must be treated like a first-time exploration
+            let effective_first_time = first_time || (family_seeding && i >= 8);
+
+            // determine direction bit
+            let dir_bit = if family_seeding && i < 8 {
+                // forced to the requested family code
+                search_state.addr_bit(i)
+            } else if effective_first_time {
+                // first time searching this bit, so always choose 0 in case of discrepancy
+                false
+            } else if i < search_state.last_discrepancy_index() {
+                // follow same path as last time in case of discrepancy
+                search_state.addr_bit(i)
+            } else if i == search_state.last_discrepancy_index() {
+                // at last discrepancy index, so now choose the other branch: 1
+                true
+            } else {
+                // unknown path, so choose 0 in case of discrepancy
+                false
+            };
+
+            // execute triplet
+            let triplet = one_wire.triplet(dir_bit, delay).await?;
+
+            // update search state
+            match triplet {
+                Triplet::Discrepancy(dir_bit) => {
+                    if effective_first_time || i > search_state.last_discrepancy_index() {
+                        // discrepancy found, so set bit in discrepancies bitflags
+                        search_state.set_discrepancy(i);
+                    } else if !effective_first_time && i == search_state.last_discrepancy_index() {
+                        // discrepancy found at last discrepancy index, so unset bit in discrepancies bitflags
+                        search_state.unset_discrepancy(i);
+                    }
+                    search_state.set_addr_bit(i, dir_bit);
+                }
+                Triplet::AllMatch(bit) => {
+                    // all devices have the same bit at this position
+                    if search_state.family_restricted && i < 8 && bit != search_state.addr_bit(i) {
+                        // every device disagrees with the requested family code here, so no
+                        // device of that family is present on the bus
+                        return Ok(None);
+                    }
+                    search_state.set_addr_bit(i, bit);
+                }
+                Triplet::NoDevicesFound => {
+                    // no devices found, so stop searching
+                    return Ok(None);
+                }
+            }
+        }
+
+        search_state.refresh_last_discrepancy_index();
+
+        crc::check_crc8(&search_state.address.to_le_bytes()).map_err(|_| ErrorKind::CrcMismatch)?;
+
+        Ok(Some((Address(search_state.address), search_state)))
+    }
+}
+
+/// Persisted state of an in-progress device search: the address bits resolved so far, the
+/// bit positions where devices diverged, and (for [`DeviceSearch::with_family`]) the family
+/// restriction being enforced.
+///
+/// Its fields are private (only the bit accessors below are public); callers only need to obtain it via [`DeviceSearch::state`] (or
+/// [`DeviceSearchAsync::state`] under the `async` feature) and hand it back to
+/// [`DeviceSearch::resume`] / [`DeviceSearchAsync::resume`] to continue enumeration later.
 #[derive(Debug, Copy, Clone)]
-struct SearchState {
+pub struct SearchState {
     // the address of the last found device
     address: u64,
 
     // bitflags of discrepancies found
     discrepancies: u64,
+
+    // bit index of the most significant unresolved discrepancy; the sentinel value 64 (past
+    // the end of the address) marks the pass that should force the low bits of `address`
+    // during a family-restricted search instead of treating them as unexplored
+    last_discrepancy_index: u8,
+
+    // whether bit positions 0..8 must match `address`'s low byte (the requested family code)
+    family_restricted: bool,
 }
 
 impl SearchState {
@@ -145,6 +428,19 @@ impl SearchState {
         SearchState {
             address: 0,
             discrepancies: 0,
+            last_discrepancy_index: 0,
+            family_restricted: false,
+        }
+    }
+
+    // seeds a search state that restricts enumeration to the given family code, per Maxim
+    // application note 187
+    fn for_family(family: u8) -> SearchState {
+        SearchState {
+            address: family as u64,
+            discrepancies: 0,
+            last_discrepancy_index: 64,
+            family_restricted: true,
         }
     }
 
@@ -165,10 +461,17 @@ impl SearchState {
     }
 
     pub fn last_discrepancy_index(&self) -> u8 {
-        if self.discrepancies == 0 {
-            return 0;
-        }
-        63 - self.discrepancies.leading_zeros() as u8
+        self.last_discrepancy_index
+    }
+
+    // recomputes the last discrepancy index from the discrepancies bitflags, discarding any
+    // sentinel seeded by `for_family` now that the pass that needed it has completed
+    fn refresh_last_discrepancy_index(&mut self) {
+        self.last_discrepancy_index = if self.discrepancies == 0 {
+            0
+        } else {
+            63 - self.discrepancies.leading_zeros() as u8
+        };
     }
 
     fn get_bit(data: u64, index: u8) -> bool {
@@ -182,4 +485,200 @@ impl SearchState {
             data & !(1 << index)
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{Error as OwError, ErrorType};
+    use crate::speed::Speed;
+
+    #[derive(Debug)]
+    struct MockError(ErrorKind);
+
+    impl OwError for MockError {
+        fn kind(&self) -> ErrorKind {
+            self.0
+        }
+    }
+
+    impl From<ErrorKind> for MockError {
+        fn from(kind: ErrorKind) -> Self {
+            MockError(kind)
+        }
+    }
+
+    struct MockDelay;
+
+    impl DelayNs for MockDelay {
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    /// A single-device bus that always answers with the bits of a fixed ROM, regardless of
+    /// the requested direction (there is never any ambiguity with only one device present).
+    struct SingleDeviceBus {
+        rom: u64,
+        bit_index: u8,
+    }
+
+    impl ErrorType for SingleDeviceBus {
+        type Error = MockError;
+    }
+
+    impl OneWire for SingleDeviceBus {
+        fn reset(&mut self, _delay: &mut impl DelayNs) -> Result<bool, Self::Error> {
+            self.bit_index = 0;
+            Ok(true)
+        }
+
+        fn read_bit(&mut self, _delay: &mut impl DelayNs) -> Result<bool, Self::Error> {
+            unimplemented!("not used by the search algorithm")
+        }
+
+        fn write_bit(&mut self, _bit: bool, _delay: &mut impl DelayNs) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn set_speed(&mut self, _speed: Speed) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn triplet(&mut self, _dir_bit: bool, _delay: &mut impl DelayNs) -> Result<Triplet, Self::Error> {
+            let bit = (self.rom >> self.bit_index) & 1 == 1;
+            self.bit_index += 1;
+            Ok(Triplet::AllMatch(bit))
+        }
+
+        fn devices<'a>(&'a mut self, delay: &'a mut impl DelayNs) -> impl Iterator<Item=Result<Address, Self::Error>> + 'a {
+            DeviceSearch::new(false, self, delay)
+        }
+
+        fn alarming_devices<'a>(&'a mut self, delay: &'a mut impl DelayNs) -> impl Iterator<Item=Result<Address, Self::Error>> + 'a {
+            DeviceSearch::new(true, self, delay)
+        }
+    }
+
+    fn rom_with_bad_crc(family: u8, serial: [u8; 6]) -> u64 {
+        let mut body = [0u8; 7];
+        body[0] = family;
+        body[1..].copy_from_slice(&serial);
+        let bad_crc = crc::crc8(&body).wrapping_add(1);
+
+        let mut bytes = [0u8; 8];
+        bytes[..7].copy_from_slice(&body);
+        bytes[7] = bad_crc;
+        u64::from_le_bytes(bytes)
+    }
+
+    #[test]
+    fn search_reports_crc_mismatch_instead_of_panicking() {
+        let mut bus = SingleDeviceBus {
+            rom: rom_with_bad_crc(0x28, [0x01, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            bit_index: 0,
+        };
+        let mut delay = MockDelay;
+
+        let mut search = DeviceSearch::new(false, &mut bus, &mut delay);
+        let result = search.next().expect("iterator should yield a result");
+
+        let err = result.expect_err("a corrupt CRC byte should be reported as an error, not panic");
+        assert_eq!(err.kind(), ErrorKind::CrcMismatch);
+    }
+
+    /// A bus simulating two devices of the same family whose serial numbers diverge at bit 8
+    /// (the first bit of the serial number, right after the forced family byte).
+    struct TwoDeviceFamilyBus {
+        roms: [u64; 2],
+        bit_index: u8,
+        branch: Option<usize>,
+    }
+
+    impl ErrorType for TwoDeviceFamilyBus {
+        type Error = MockError;
+    }
+
+    impl OneWire for TwoDeviceFamilyBus {
+        fn reset(&mut self, _delay: &mut impl DelayNs) -> Result<bool, Self::Error> {
+            self.bit_index = 0;
+            self.branch = None;
+            Ok(true)
+        }
+
+        fn read_bit(&mut self, _delay: &mut impl DelayNs) -> Result<bool, Self::Error> {
+            unimplemented!("not used by the search algorithm")
+        }
+
+        fn write_bit(&mut self, _bit: bool, _delay: &mut impl DelayNs) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn set_speed(&mut self, _speed: Speed) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn triplet(&mut self, dir_bit: bool, _delay: &mut impl DelayNs) -> Result<Triplet, Self::Error> {
+            let bits: [bool; 2] = self.roms.map(|rom| (rom >> self.bit_index) & 1 == 1);
+            self.bit_index += 1;
+
+            let triplet = match self.branch {
+                // still following both devices: report a genuine discrepancy if they differ
+                None if bits[0] != bits[1] => Triplet::Discrepancy(dir_bit),
+                None => Triplet::AllMatch(bits[0]),
+                // a branch was already chosen; only the matching device remains on this path
+                Some(chosen) => Triplet::AllMatch(bits[chosen]),
+            };
+
+            if matches!(triplet, Triplet::Discrepancy(_)) {
+                // only the device(s) agreeing with the chosen direction remain on this path
+                self.branch = Some(if bits[0] == dir_bit { 0 } else { 1 });
+            }
+
+            Ok(triplet)
+        }
+
+        fn devices<'a>(&'a mut self, delay: &'a mut impl DelayNs) -> impl Iterator<Item=Result<Address, Self::Error>> + 'a {
+            DeviceSearch::new(false, self, delay)
+        }
+
+        fn alarming_devices<'a>(&'a mut self, delay: &'a mut impl DelayNs) -> impl Iterator<Item=Result<Address, Self::Error>> + 'a {
+            DeviceSearch::new(true, self, delay)
+        }
+    }
+
+    #[test]
+    fn with_family_finds_every_device_of_the_family() {
+        let roms = [
+            valid_rom(0x28, [0x01, 0x00, 0x00, 0x00, 0x00, 0x00]),
+            valid_rom(0x28, [0x02, 0x00, 0x00, 0x00, 0x00, 0x00]),
+        ];
+        let mut bus = TwoDeviceFamilyBus {
+            roms,
+            bit_index: 0,
+            branch: None,
+        };
+        let mut delay = MockDelay;
+
+        let mut search = DeviceSearch::with_family(0x28, false, &mut bus, &mut delay);
+        let first = search.next().expect("first device should be found").expect("CRC should be valid");
+        let second = search.next().expect("second device should be found").expect("CRC should be valid");
+        assert!(search.next().is_none(), "only two devices are on the bus");
+
+        let mut found = [first.0, second.0];
+        found.sort_unstable();
+        let mut expected = roms;
+        expected.sort_unstable();
+        assert_eq!(found, expected);
+    }
+
+    fn valid_rom(family: u8, serial: [u8; 6]) -> u64 {
+        let mut body = [0u8; 7];
+        body[0] = family;
+        body[1..].copy_from_slice(&serial);
+        let crc = crc::crc8(&body);
+
+        let mut bytes = [0u8; 8];
+        bytes[..7].copy_from_slice(&body);
+        bytes[7] = crc;
+        u64::from_le_bytes(bytes)
+    }
+}
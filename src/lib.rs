@@ -1,16 +1,20 @@
 //! A hardware abstraction layer (HAL) for the 1-Wire protocol.
 
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 // #![warn(missing_docs)]
 
 use embedded_hal::delay::DelayNs;
+#[cfg(feature = "async")]
+use embedded_hal_async::delay::DelayNs as AsyncDelayNs;
 use crate::address::Address;
-use crate::error::ErrorType;
+use crate::error::{ErrorKind, ErrorType};
+use crate::speed::Speed;
 use crate::triplet::Triplet;
 
 pub mod error;
 pub mod address;
 pub mod commands;
+pub mod speed;
 pub mod triplet;
 
 pub mod device_search;
@@ -45,6 +49,19 @@ pub trait OneWire: ErrorType {
         Ok(())
     }
 
+    /// Reads `output.len()` data bytes followed by their inverted CRC-16, verifying the
+    /// checksum. This gives scratchpad/memory-page reads (e.g. on the DS2408/DS2423/DS28EC20)
+    /// the same end-to-end integrity checking that [`Self::read_address`] gives ROM reads.
+    fn read_bytes_checked(&mut self, output: &mut [u8], delay: &mut impl DelayNs) -> Result<(), Self::Error>
+        where Self::Error: From<ErrorKind>,
+    {
+        self.read_bytes(output, delay)?;
+        let mut crc_bytes = [0u8; 2];
+        self.read_bytes(&mut crc_bytes, delay)?;
+        crc::check_crc16(output, crc_bytes).map_err(|_| ErrorKind::CrcMismatch)?;
+        Ok(())
+    }
+
     /// Writes a single bit to the bus.
     fn write_bit(&mut self, bit: bool, delay: &mut impl DelayNs) -> Result<(), Self::Error>;
 
@@ -65,14 +82,17 @@ pub trait OneWire: ErrorType {
         Ok(())
     }
 
-    /// Reads the ROM of the single device on the bus.
+    /// Reads the ROM of the single device on the bus, verifying its CRC-8.
     /// This only works for a single device on the bus.
-    fn read_address(&mut self, delay: &mut impl DelayNs) -> Result<Address, Self::Error> {
+    fn read_address(&mut self, delay: &mut impl DelayNs) -> Result<Address, Self::Error>
+        where Self::Error: From<ErrorKind>,
+    {
         self.write_byte(commands::READ_ROM, delay)?;
         let mut rom: [u8; 8] = [0; 8];
         for i in 0..8 {
             rom[i] = self.read_byte(delay)?;
         }
+        crc::check_crc8(&rom).map_err(|_| ErrorKind::CrcMismatch)?;
         Ok(Address(u64::from_le_bytes(rom)))
     }
 
@@ -91,6 +111,32 @@ pub trait OneWire: ErrorType {
         Ok(())
     }
 
+    /// Switches the timing profile used for subsequent bus operations.
+    /// Devices must already be in the corresponding mode (see [`Self::overdrive_skip_address`]
+    /// and [`Self::overdrive_match_address`]) before switching to [`Speed::Overdrive`], and a
+    /// bus reset always returns every device to [`Speed::Standard`].
+    fn set_speed(&mut self, speed: Speed) -> Result<(), Self::Error>;
+
+    /// Switches all devices on the bus into Overdrive mode and addresses them all
+    /// simultaneously. This should only be called after a reset, and should be immediately
+    /// followed by another command sent at [`Speed::Overdrive`].
+    fn overdrive_skip_address(&mut self, delay: &mut impl DelayNs) -> Result<(), Self::Error> {
+        self.write_byte(commands::OVERDRIVE_SKIP_ROM, delay)?;
+        self.set_speed(Speed::Overdrive)
+    }
+
+    /// Switches a specific device into Overdrive mode and addresses it by its ROM code. All
+    /// others will wait for a reset pulse.
+    /// The command byte is sent at standard speed, per the device datasheet; the bus then
+    /// switches to [`Speed::Overdrive`] before the address is sent. This should only be called
+    /// after a reset, and should be immediately followed by another command.
+    fn overdrive_match_address(&mut self, address: &Address, delay: &mut impl DelayNs) -> Result<(), Self::Error> {
+        self.write_byte(commands::OVERDRIVE_MATCH_ROM, delay)?;
+        self.set_speed(Speed::Overdrive)?;
+        self.write_bytes(&address.0.to_le_bytes(), delay)?;
+        Ok(())
+    }
+
     /// Sends a reset, followed with either a SKIP_ROM or MATCH_ROM (with an address), and then the supplied command.
     /// This should be followed by any reading/writing, if needed by the command used.
     fn send_command(&mut self, command: u8, address: Option<&Address>, delay: &mut impl DelayNs) -> Result<(), Self::Error> {
@@ -141,3 +187,271 @@ pub trait OneWire: ErrorType {
     //     DeviceSearch::new(true, self, delay)
     // }
 }
+
+/// Async HAL trait for the 1-Wire protocol.
+///
+/// Mirrors [`OneWire`], but is driven by [`embedded_hal_async::delay::DelayNs`] so that the
+/// long reset and conversion delays inherent to the 1-Wire protocol don't block the executor.
+#[cfg(feature = "async")]
+pub trait OneWireAsync: ErrorType {
+    /// Resets the 1-Wire bus.
+    /// Returns true if a device responded with a presence pulse.
+    async fn reset(&mut self, delay: &mut impl AsyncDelayNs) -> Result<bool, Self::Error>;
+
+    /// Reads a single bit from the bus.
+    async fn read_bit(&mut self, delay: &mut impl AsyncDelayNs) -> Result<bool, Self::Error>;
+
+    /// Reads a single byte from the bus.
+    async fn read_byte(&mut self, delay: &mut impl AsyncDelayNs) -> Result<u8, Self::Error> {
+        let mut output: u8 = 0;
+        for _ in 0..8 {
+            output >>= 1;
+            if self.read_bit(delay).await? {
+                output |= 0x80;
+            }
+        }
+        Ok(output)
+    }
+
+    /// Reads multiple bytes from the bus.
+    async fn read_bytes(&mut self, output: &mut [u8], delay: &mut impl AsyncDelayNs) -> Result<(), Self::Error> {
+        for i in 0..output.len() {
+            output[i] = self.read_byte(delay).await?;
+        }
+        Ok(())
+    }
+
+    /// Reads `output.len()` data bytes followed by their inverted CRC-16, verifying the
+    /// checksum. This gives scratchpad/memory-page reads (e.g. on the DS2408/DS2423/DS28EC20)
+    /// the same end-to-end integrity checking that [`Self::read_address`] gives ROM reads.
+    async fn read_bytes_checked(&mut self, output: &mut [u8], delay: &mut impl AsyncDelayNs) -> Result<(), Self::Error>
+        where Self::Error: From<ErrorKind>,
+    {
+        self.read_bytes(output, delay).await?;
+        let mut crc_bytes = [0u8; 2];
+        self.read_bytes(&mut crc_bytes, delay).await?;
+        crc::check_crc16(output, crc_bytes).map_err(|_| ErrorKind::CrcMismatch)?;
+        Ok(())
+    }
+
+    /// Writes a single bit to the bus.
+    async fn write_bit(&mut self, bit: bool, delay: &mut impl AsyncDelayNs) -> Result<(), Self::Error>;
+
+    /// Writes a single byte to the bus.
+    async fn write_byte(&mut self, mut value: u8, delay: &mut impl AsyncDelayNs) -> Result<(), Self::Error> {
+        for _ in 0..8 {
+            self.write_bit(value & 0x01 == 0x01, delay).await?;
+            value >>= 1;
+        }
+        Ok(())
+    }
+
+    /// Writes multiple bytes to the bus.
+    async fn write_bytes(&mut self, bytes: &[u8], delay: &mut impl AsyncDelayNs) -> Result<(), Self::Error> {
+        for i in 0..bytes.len() {
+            self.write_byte(bytes[i], delay).await?;
+        }
+        Ok(())
+    }
+
+    /// Reads the ROM of the single device on the bus, verifying its CRC-8.
+    /// This only works for a single device on the bus.
+    async fn read_address(&mut self, delay: &mut impl AsyncDelayNs) -> Result<Address, Self::Error>
+        where Self::Error: From<ErrorKind>,
+    {
+        self.write_byte(commands::READ_ROM, delay).await?;
+        let mut rom: [u8; 8] = [0; 8];
+        for i in 0..8 {
+            rom[i] = self.read_byte(delay).await?;
+        }
+        crc::check_crc8(&rom).map_err(|_| ErrorKind::CrcMismatch)?;
+        Ok(Address(u64::from_le_bytes(rom)))
+    }
+
+    /// Address a specific device. All others will wait for a reset pulse.
+    /// This should only be called after a reset, and should be immediately followed by another command.
+    async fn match_address(&mut self, address: &Address, delay: &mut impl AsyncDelayNs) -> Result<(), Self::Error> {
+        self.write_byte(commands::MATCH_ROM, delay).await?;
+        self.write_bytes(&address.0.to_le_bytes(), delay).await?;
+        Ok(())
+    }
+
+    /// Address all devices on the bus simultaneously.
+    /// This should only be called after a reset, and should be immediately followed by another command.
+    async fn skip_address(&mut self, delay: &mut impl AsyncDelayNs) -> Result<(), Self::Error> {
+        self.write_byte(commands::SKIP_ROM, delay).await
+    }
+
+    /// Switches the timing profile used for subsequent bus operations.
+    /// Devices must already be in the corresponding mode (see [`Self::overdrive_skip_address`]
+    /// and [`Self::overdrive_match_address`]) before switching to [`Speed::Overdrive`], and a
+    /// bus reset always returns every device to [`Speed::Standard`].
+    async fn set_speed(&mut self, speed: Speed) -> Result<(), Self::Error>;
+
+    /// Switches all devices on the bus into Overdrive mode and addresses them all
+    /// simultaneously. This should only be called after a reset, and should be immediately
+    /// followed by another command sent at [`Speed::Overdrive`].
+    async fn overdrive_skip_address(&mut self, delay: &mut impl AsyncDelayNs) -> Result<(), Self::Error> {
+        self.write_byte(commands::OVERDRIVE_SKIP_ROM, delay).await?;
+        self.set_speed(Speed::Overdrive).await
+    }
+
+    /// Switches a specific device into Overdrive mode and addresses it by its ROM code. All
+    /// others will wait for a reset pulse.
+    /// The command byte is sent at standard speed, per the device datasheet; the bus then
+    /// switches to [`Speed::Overdrive`] before the address is sent. This should only be called
+    /// after a reset, and should be immediately followed by another command.
+    async fn overdrive_match_address(&mut self, address: &Address, delay: &mut impl AsyncDelayNs) -> Result<(), Self::Error> {
+        self.write_byte(commands::OVERDRIVE_MATCH_ROM, delay).await?;
+        self.set_speed(Speed::Overdrive).await?;
+        self.write_bytes(&address.0.to_le_bytes(), delay).await?;
+        Ok(())
+    }
+
+    /// Sends a reset, followed with either a SKIP_ROM or MATCH_ROM (with an address), and then the supplied command.
+    /// This should be followed by any reading/writing, if needed by the command used.
+    async fn send_command(&mut self, command: u8, address: Option<&Address>, delay: &mut impl AsyncDelayNs) -> Result<(), Self::Error> {
+        self.reset(delay).await?;
+        if let Some(address) = address {
+            self.match_address(address, delay).await?;
+        } else {
+            self.skip_address(delay).await?;
+        }
+        self.write_byte(command, delay).await?;
+        Ok(())
+    }
+
+    /// Generates three time slots on the bus: two read slots and one write slot.
+    /// See [`OneWire::triplet`] for the full timing/decision table.
+    async fn triplet(&mut self, dir_bit: bool, delay: &mut impl AsyncDelayNs) -> Result<Triplet, Self::Error>;
+
+    /// Returns a cursor that asynchronously walks all device addresses on the bus, one
+    /// `next().await` at a time.
+    /// There is no requirement to immediately finish iterating all devices, but if devices are
+    /// added; are removed or change their alarm state, the search may return an error or fail to find a device.
+    /// Device addresses will always be returned in the same order (lowest to highest, Little Endian).
+    fn devices<'a, D>(&'a mut self, delay: &'a mut D) -> device_search::DeviceSearchAsync<'a, Self, D>
+        where Self: Sized,
+              Self::Error: From<ErrorKind>,
+              D: AsyncDelayNs,
+    {
+        device_search::DeviceSearchAsync::new(false, self, delay)
+    }
+
+    /// Returns a cursor that asynchronously walks all alarming device addresses on the bus, one
+    /// `next().await` at a time.
+    /// There is no requirement to immediately finish iterating all devices, but if devices are
+    /// added; are removed or change their alarm state, the search may return an error or fail to find a device.
+    /// Device addresses will always be returned in the same order (lowest to highest, Little Endian).
+    fn alarming_devices<'a, D>(&'a mut self, delay: &'a mut D) -> device_search::DeviceSearchAsync<'a, Self, D>
+        where Self: Sized,
+              Self::Error: From<ErrorKind>,
+              D: AsyncDelayNs,
+    {
+        device_search::DeviceSearchAsync::new(true, self, delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error as OwError;
+
+    #[derive(Debug)]
+    struct MockError(ErrorKind);
+
+    impl OwError for MockError {
+        fn kind(&self) -> ErrorKind {
+            self.0
+        }
+    }
+
+    impl From<ErrorKind> for MockError {
+        fn from(kind: ErrorKind) -> Self {
+            MockError(kind)
+        }
+    }
+
+    struct MockDelay;
+
+    impl DelayNs for MockDelay {
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    /// A bus whose read slots return the bits of a fixed byte sequence, least-significant bit
+    /// first; its triplet is not implemented.
+    struct FixedBytesBus {
+        bytes: Vec<u8>,
+        bit_index: usize,
+    }
+
+    impl ErrorType for FixedBytesBus {
+        type Error = MockError;
+    }
+
+    impl OneWire for FixedBytesBus {
+        fn reset(&mut self, _delay: &mut impl DelayNs) -> Result<bool, Self::Error> {
+            self.bit_index = 0;
+            Ok(true)
+        }
+
+        fn read_bit(&mut self, _delay: &mut impl DelayNs) -> Result<bool, Self::Error> {
+            let byte = self.bytes[self.bit_index / 8];
+            let bit = (byte >> (self.bit_index % 8)) & 0x01 == 0x01;
+            self.bit_index += 1;
+            Ok(bit)
+        }
+
+        fn write_bit(&mut self, _bit: bool, _delay: &mut impl DelayNs) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn set_speed(&mut self, _speed: Speed) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn triplet(&mut self, _dir_bit: bool, _delay: &mut impl DelayNs) -> Result<Triplet, Self::Error> {
+            unimplemented!("not used by read_bytes_checked")
+        }
+
+        fn devices<'a>(&'a mut self, delay: &'a mut impl DelayNs) -> impl Iterator<Item=Result<Address, Self::Error>> + 'a {
+            device_search::DeviceSearch::new(false, self, delay)
+        }
+
+        fn alarming_devices<'a>(&'a mut self, delay: &'a mut impl DelayNs) -> impl Iterator<Item=Result<Address, Self::Error>> + 'a {
+            device_search::DeviceSearch::new(true, self, delay)
+        }
+    }
+
+    fn bus_with_payload(data: &[u8], crc_bytes: [u8; 2]) -> FixedBytesBus {
+        let mut bytes = data.to_vec();
+        bytes.extend_from_slice(&crc_bytes);
+        FixedBytesBus { bytes, bit_index: 0 }
+    }
+
+    #[test]
+    fn read_bytes_checked_accepts_a_correct_trailing_crc16() {
+        let data = [0x01, 0x02, 0x03, 0x04];
+        let crc_bytes = (crc::crc16(&data) ^ 0xFFFF).to_le_bytes();
+        let mut bus = bus_with_payload(&data, crc_bytes);
+        let mut delay = MockDelay;
+
+        let mut output = [0u8; 4];
+        bus.read_bytes_checked(&mut output, &mut delay).expect("CRC-16 should match");
+        assert_eq!(output, data);
+    }
+
+    #[test]
+    fn read_bytes_checked_reports_crc_mismatch_instead_of_panicking() {
+        let data = [0x01, 0x02, 0x03, 0x04];
+        let mut crc_bytes = (crc::crc16(&data) ^ 0xFFFF).to_le_bytes();
+        crc_bytes[0] ^= 0xFF;
+        let mut bus = bus_with_payload(&data, crc_bytes);
+        let mut delay = MockDelay;
+
+        let mut output = [0u8; 4];
+        let err = bus.read_bytes_checked(&mut output, &mut delay)
+            .expect_err("a corrupt trailing CRC-16 should be reported as an error, not panic");
+        assert_eq!(err.kind(), ErrorKind::CrcMismatch);
+    }
+}
@@ -0,0 +1,11 @@
+//! Bus timing profiles supported by the 1-Wire protocol.
+
+/// The timing profile used for the bus's time slots.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Speed {
+    /// Standard speed (~16.3 kbps).
+    Standard,
+    /// Overdrive speed (~142 kbps), selected via `OVERDRIVE_SKIP_ROM` / `OVERDRIVE_MATCH_ROM`.
+    Overdrive,
+}